@@ -0,0 +1,53 @@
+use std::path::PathBuf;
+
+use clap::Args;
+
+use crate::commands::format::{LineRange, NewlineStyle, OutputFormat};
+
+/// Arguments passed via the CLI that override the resolved configuration, independent of any
+/// particular command.
+#[derive(Debug, Default)]
+pub(crate) struct CliOverrides;
+
+/// Arguments for the `ruff format` command.
+#[derive(Debug, Args)]
+pub(crate) struct FormatArguments {
+    /// Files to format.
+    pub(crate) files: Vec<PathBuf>,
+
+    /// Avoid writing any formatted files back; instead, exit with a non-zero status code if any
+    /// file would have been reformatted.
+    #[arg(long)]
+    pub(crate) check: bool,
+
+    /// Avoid writing any formatted files back; instead, print the diff that would result from
+    /// formatting.
+    #[arg(long, conflicts_with = "check")]
+    pub(crate) diff: bool,
+
+    /// Format only the given line ranges, e.g. `--line-ranges 1-5 --line-ranges 10-12`. Lines
+    /// outside every requested range are preserved byte-for-byte. May be passed more than once.
+    #[arg(long = "line-ranges")]
+    pub(crate) line_ranges: Vec<LineRange>,
+
+    /// The line ending to use when writing formatted source, overriding the input's own line
+    /// ending.
+    #[arg(long, value_enum, default_value_t)]
+    pub(crate) line_ending: NewlineStyle,
+
+    /// The format to emit formatting results in.
+    #[arg(long, value_enum, default_value_t)]
+    pub(crate) output_format: OutputFormat,
+
+    /// Ignore all configuration files.
+    #[arg(long)]
+    pub(crate) isolated: bool,
+
+    /// Path to a `pyproject.toml` or `ruff.toml` configuration file.
+    #[arg(long)]
+    pub(crate) config: Option<PathBuf>,
+
+    /// The name of the file when formatting source code read from `stdin`.
+    #[arg(long)]
+    pub(crate) stdin_filename: Option<PathBuf>,
+}
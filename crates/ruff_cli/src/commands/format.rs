@@ -0,0 +1,49 @@
+use std::io;
+use std::path::PathBuf;
+
+use rustpython_parser::error::ParseError;
+use thiserror::Error;
+
+use ruff_python_formatter::FormatModuleError;
+
+mod diff;
+mod emitter;
+mod line_ranges;
+mod newline;
+
+pub(crate) use diff::unified_diff;
+pub(crate) use emitter::{Emitter, OutputFormat};
+pub(crate) use line_ranges::{format_line_ranges, LineRange};
+pub(crate) use newline::NewlineStyle;
+
+/// The requested treatment of the diff between the formatted and unformatted source.
+#[derive(Debug, Clone, Copy, is_macro::Is)]
+pub(crate) enum FormatMode {
+    /// Write the formatted contents back to the file (or stdout).
+    Write,
+    /// Check if the file is formatted, without writing anything back.
+    Check,
+    /// Print a unified diff of the formatting changes, without writing anything back.
+    Diff,
+}
+
+/// The outcome of formatting a single file.
+#[derive(Debug, Clone, Copy, is_macro::Is)]
+pub(crate) enum FormatCommandResult {
+    /// The file was formatted (or would be, in [`FormatMode::Check`] and [`FormatMode::Diff`]).
+    Formatted,
+    /// The file was already formatted.
+    Unchanged,
+}
+
+#[derive(Error, Debug)]
+pub(crate) enum FormatCommandError {
+    #[error("Failed to read {0:?}: {1}")]
+    Read(Option<PathBuf>, io::Error),
+    #[error("Failed to write {0:?}: {1}")]
+    Write(Option<PathBuf>, io::Error),
+    #[error("Failed to format {0:?}: {1}")]
+    FormatModule(Option<PathBuf>, FormatModuleError),
+    #[error("Failed to parse {0:?}: {1}")]
+    Parse(Option<PathBuf>, ParseError),
+}
@@ -0,0 +1,316 @@
+//! Line-based diffing shared by `ruff format`'s human-readable and machine-readable changesets.
+
+use std::fmt::Write;
+use std::ops::Range;
+use std::path::Path;
+
+/// Number of context lines shown around each change, mirroring `diff -u`.
+const CONTEXT_LINES: usize = 3;
+
+/// The largest `original.len() * modified.len()` [`longest_common_subsequence`] will allocate a
+/// DP table for, before falling back to reporting the whole file as changed. At `u32` cells, this
+/// caps the table at 16MB; above it, the allocation grows unbounded with file size, which is a
+/// real OOM/latency risk for the multi-thousand-line generated or vendored files `ruff format` is
+/// expected to run over in CI.
+const MAX_LCS_CELLS: usize = 4_000_000;
+
+/// Splits `source` into lines the way [`str::lines`] does, except that a trailing `\r` is kept as
+/// part of the line's content rather than stripped.
+///
+/// Diffing must be able to tell a line whose only change is its line ending (e.g. after
+/// `--line-ending windows` on an all-LF file) from a truly unchanged line; [`str::lines`]
+/// normalizes `\r\n` and `\n` identically, which would hide that change entirely.
+pub(crate) fn split_lines(source: &str) -> Vec<&str> {
+    let mut lines: Vec<&str> = source.split('\n').collect();
+    if lines.last() == Some(&"") {
+        lines.pop();
+    }
+    lines
+}
+
+/// A contiguous run of lines that differ between the original and formatted source.
+#[derive(Debug, Clone)]
+pub(crate) struct DiffLineGroup {
+    /// The 0-based, exclusive range of lines removed from the original source.
+    pub(crate) removed: Range<usize>,
+    /// The lines that replace `removed`, taken from the formatted source.
+    pub(crate) added: Vec<String>,
+}
+
+/// Computes the smallest set of [`DiffLineGroup`]s needed to turn `original` into `modified`.
+///
+/// Uses a textbook LCS dynamic-programming diff over lines, which is quadratic in the number of
+/// lines and more than fast enough for most source files `ruff format` operates on &mdash; except
+/// that "most" isn't "all": above [`MAX_LCS_CELLS`], this falls back to reporting the whole file
+/// as a single change rather than risking an unbounded allocation.
+pub(crate) fn diff_lines(original: &[&str], modified: &[&str]) -> Vec<DiffLineGroup> {
+    if original == modified {
+        return Vec::new();
+    }
+
+    if original.len().saturating_mul(modified.len()) > MAX_LCS_CELLS {
+        return vec![DiffLineGroup {
+            removed: 0..original.len(),
+            added: modified.iter().map(|&line| line.to_string()).collect(),
+        }];
+    }
+
+    let lcs = longest_common_subsequence(original, modified);
+
+    let mut groups = Vec::new();
+    let mut orig_idx = 0;
+    let mut mod_idx = 0;
+
+    for (lcs_orig, lcs_mod) in lcs {
+        if lcs_orig > orig_idx || lcs_mod > mod_idx {
+            groups.push(DiffLineGroup {
+                removed: orig_idx..lcs_orig,
+                added: modified[mod_idx..lcs_mod]
+                    .iter()
+                    .map(|&line| line.to_string())
+                    .collect(),
+            });
+        }
+        orig_idx = lcs_orig + 1;
+        mod_idx = lcs_mod + 1;
+    }
+
+    if orig_idx < original.len() || mod_idx < modified.len() {
+        groups.push(DiffLineGroup {
+            removed: orig_idx..original.len(),
+            added: modified[mod_idx..]
+                .iter()
+                .map(|&line| line.to_string())
+                .collect(),
+        });
+    }
+
+    groups
+}
+
+/// Returns, for each line common to `a` and `b`, the pair of indices at which it occurs.
+fn longest_common_subsequence(a: &[&str], b: &[&str]) -> Vec<(usize, usize)> {
+    let mut table = vec![vec![0u32; b.len() + 1]; a.len() + 1];
+    for i in (0..a.len()).rev() {
+        for j in (0..b.len()).rev() {
+            table[i][j] = if a[i] == b[j] {
+                table[i + 1][j + 1] + 1
+            } else {
+                table[i + 1][j].max(table[i][j + 1])
+            };
+        }
+    }
+
+    let mut pairs = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() && j < b.len() {
+        if a[i] == b[j] {
+            pairs.push((i, j));
+            i += 1;
+            j += 1;
+        } else if table[i + 1][j] >= table[i][j + 1] {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+    pairs
+}
+
+/// A group of [`DiffLineGroup`]s close enough together that their context windows overlap, and
+/// so must be emitted as a single `@@` hunk rather than as separate, overlapping ones.
+struct Hunk {
+    /// The 0-based, inclusive-exclusive range of original lines shown by this hunk, including
+    /// context.
+    context: Range<usize>,
+    /// The indices into the originating `groups` slice that this hunk covers.
+    groups: Range<usize>,
+}
+
+/// Merges `groups` into [`Hunk`]s, combining any whose context windows (`CONTEXT_LINES` on
+/// either side of the change) overlap or touch, exactly as `diff -u` does.
+fn merge_hunks(groups: &[DiffLineGroup], original_len: usize) -> Vec<Hunk> {
+    let mut hunks: Vec<Hunk> = Vec::new();
+
+    for (index, group) in groups.iter().enumerate() {
+        let context_start = group.removed.start.saturating_sub(CONTEXT_LINES);
+        let context_end = (group.removed.end + CONTEXT_LINES).min(original_len);
+
+        match hunks.last_mut() {
+            Some(hunk) if context_start <= hunk.context.end => {
+                hunk.context.end = hunk.context.end.max(context_end);
+                hunk.groups.end = index + 1;
+            }
+            _ => hunks.push(Hunk {
+                context: context_start..context_end,
+                groups: index..index + 1,
+            }),
+        }
+    }
+
+    hunks
+}
+
+/// Formats `original` and `modified` as a unified diff, in the style of `diff -u`.
+pub(crate) fn unified_diff(path: Option<&Path>, original: &str, modified: &str) -> String {
+    let original_lines = split_lines(original);
+    let modified_lines = split_lines(modified);
+    let groups = diff_lines(&original_lines, &modified_lines);
+    let hunks = merge_hunks(&groups, original_lines.len());
+
+    let mut output = String::new();
+    if let Some(path) = path {
+        let _ = writeln!(output, "--- {}", path.display());
+        let _ = writeln!(output, "+++ {}", path.display());
+    }
+
+    // Tracks how much the modified side has grown or shrunk relative to the original so far, so
+    // that later hunks report the correct starting line on the `+` side.
+    let mut mod_offset: isize = 0;
+
+    for hunk in &hunks {
+        let hunk_groups = &groups[hunk.groups.clone()];
+
+        let removed_count = hunk.context.end - hunk.context.start;
+        let added_delta: isize = hunk_groups
+            .iter()
+            .map(|group| group.added.len() as isize - (group.removed.end - group.removed.start) as isize)
+            .sum();
+        let added_count = (removed_count as isize + added_delta) as usize;
+
+        let orig_start_line = hunk.context.start + 1;
+        let mod_start_line = (hunk.context.start + 1) as isize + mod_offset;
+
+        let _ = writeln!(
+            output,
+            "@@ -{orig_start_line},{removed_count} +{mod_start_line},{added_count} @@"
+        );
+
+        let mut cursor = hunk.context.start;
+        for group in hunk_groups {
+            for line in &original_lines[cursor..group.removed.start] {
+                let _ = writeln!(output, " {line}");
+            }
+            for line in &original_lines[group.removed.clone()] {
+                let _ = writeln!(output, "-{line}");
+            }
+            for line in &group.added {
+                let _ = writeln!(output, "+{line}");
+            }
+            cursor = group.removed.end;
+        }
+        for line in &original_lines[cursor..hunk.context.end] {
+            let _ = writeln!(output, " {line}");
+        }
+
+        mod_offset += added_delta;
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn diff_lines_detects_single_change() {
+        let original = vec!["a", "b", "c"];
+        let modified = vec!["a", "x", "c"];
+        let groups = diff_lines(&original, &modified);
+
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].removed, 1..2);
+        assert_eq!(groups[0].added, vec!["x".to_string()]);
+    }
+
+    #[test]
+    fn diff_lines_handles_no_changes() {
+        let lines = vec!["a", "b", "c"];
+        assert!(diff_lines(&lines, &lines).is_empty());
+    }
+
+    #[test]
+    fn split_lines_keeps_trailing_cr_as_line_content() {
+        assert_eq!(split_lines("a\r\nb\n"), vec!["a\r", "b"]);
+        assert_eq!(split_lines("a\nb\n"), vec!["a", "b"]);
+    }
+
+    #[test]
+    fn diff_lines_detects_a_line_ending_only_change() {
+        // `--line-ending windows` on an all-LF file changes every line's terminator and nothing
+        // else; `split_lines` must keep that visible instead of normalizing `\r\n` and `\n` alike.
+        let original = split_lines("a\nb\n");
+        let modified = split_lines("a\r\nb\r\n");
+
+        let groups = diff_lines(&original, &modified);
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].removed, 0..2);
+        assert_eq!(groups[0].added, vec!["a\r".to_string(), "b\r".to_string()]);
+    }
+
+    #[test]
+    fn diff_lines_falls_back_to_a_single_group_for_huge_inputs() {
+        // Exceeds `MAX_LCS_CELLS`, so this must skip the quadratic LCS table entirely rather than
+        // allocating one, and report the whole file as a single change instead.
+        let side_length = 2100;
+        let original_owned: Vec<String> = (0..side_length).map(|n| format!("line {n}")).collect();
+        let mut modified_owned = original_owned.clone();
+        modified_owned[0] = "changed".to_string();
+
+        let original: Vec<&str> = original_owned.iter().map(String::as_str).collect();
+        let modified: Vec<&str> = modified_owned.iter().map(String::as_str).collect();
+
+        let groups = diff_lines(&original, &modified);
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].removed, 0..side_length);
+        assert_eq!(groups[0].added.len(), side_length);
+    }
+
+    #[test]
+    fn unified_diff_reports_a_line_ending_only_change_as_a_hunk() {
+        let diff = unified_diff(None, "a\nb\n", "a\r\nb\r\n");
+        assert!(
+            diff.lines().any(|line| line.starts_with("@@")),
+            "expected a hunk reporting the changed line endings, got an empty diff:\n{diff}"
+        );
+    }
+
+    #[test]
+    fn unified_diff_merges_close_hunks_into_one() {
+        // Changes on lines 2 and 6 (1-based) are closer together than `2 * CONTEXT_LINES`, so
+        // their context windows overlap and must be merged into a single `@@` hunk rather than
+        // emitted as two overlapping ones.
+        let original = "1\n2\n3\n4\n5\n6\n7\n8\n9\n";
+        let modified = "1\nTWO\n3\n4\n5\nSIX\n7\n8\n9\n";
+
+        let diff = unified_diff(None, original, modified);
+        let hunk_headers = diff.lines().filter(|line| line.starts_with("@@")).count();
+
+        assert_eq!(hunk_headers, 1, "expected a single merged hunk:\n{diff}");
+        // Every original line should appear as context or a removal exactly once.
+        for line in ["1", "2", "3", "4", "5", "6", "7", "8", "9"] {
+            let occurrences = diff
+                .lines()
+                .filter(|diff_line| diff_line.trim_start_matches(['+', '-', ' ']) == line)
+                .count();
+            assert_eq!(occurrences, 1, "line {line} appeared more than once:\n{diff}");
+        }
+    }
+
+    #[test]
+    fn unified_diff_keeps_distant_hunks_separate() {
+        let original_lines: Vec<String> = (1..=40).map(|n| n.to_string()).collect();
+        let mut modified_lines = original_lines.clone();
+        modified_lines[1] = "TWO".to_string();
+        modified_lines[35] = "THIRTY-SIX".to_string();
+
+        let original = original_lines.join("\n") + "\n";
+        let modified = modified_lines.join("\n") + "\n";
+
+        let diff = unified_diff(None, &original, &modified);
+        let hunk_headers = diff.lines().filter(|line| line.starts_with("@@")).count();
+
+        assert_eq!(hunk_headers, 2, "expected two separate hunks:\n{diff}");
+    }
+}
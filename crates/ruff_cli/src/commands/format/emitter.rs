@@ -0,0 +1,391 @@
+//! Pluggable output for `ruff format`, mirroring rustfmt's `Emitter`/`EmitMode` abstraction.
+
+use std::fmt::Write as _;
+use std::io::{stdout, Write};
+use std::path::Path;
+
+use clap::ValueEnum;
+
+use super::diff::{diff_lines, split_lines};
+use super::{unified_diff, FormatCommandError, FormatCommandResult, FormatMode};
+
+/// Reports the outcome of formatting a single file.
+///
+/// Implementations decide both *what* to report (a rewritten file, a diff, a structured record)
+/// and *when* to flush it; [`Emitter::finish`] is called once after every file has been emitted,
+/// giving emitters that buffer output (like [`JsonEmitter`]) a chance to write it out.
+pub(crate) trait Emitter {
+    fn emit(
+        &mut self,
+        path: Option<&Path>,
+        mode: FormatMode,
+        original: &str,
+        formatted: &str,
+        result: FormatCommandResult,
+    ) -> Result<(), FormatCommandError>;
+
+    fn finish(&mut self) -> Result<(), FormatCommandError> {
+        Ok(())
+    }
+}
+
+/// The user-facing choice of [`Emitter`], threaded through `--output-format`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub(crate) enum OutputFormat {
+    /// Write the formatted contents, diff, or nothing to stdout, depending on [`FormatMode`].
+    #[default]
+    Text,
+    /// Emit a single JSON array of `{ "name": ..., "diff": [...] }` records.
+    Json,
+    /// Emit a checkstyle XML report, for consumption by CI systems.
+    Checkstyle,
+    /// Emit a stable, per-chunk changeset for editors to apply without re-reading the whole file.
+    ModifiedLines,
+}
+
+impl std::fmt::Display for OutputFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        self.to_possible_value()
+            .expect("no values are skipped")
+            .get_name()
+            .fmt(f)
+    }
+}
+
+impl OutputFormat {
+    pub(crate) fn to_emitter(self) -> Box<dyn Emitter> {
+        match self {
+            OutputFormat::Text => Box::<StdoutEmitter>::default(),
+            OutputFormat::Json => Box::<JsonEmitter>::default(),
+            OutputFormat::Checkstyle => Box::<CheckstyleEmitter>::default(),
+            OutputFormat::ModifiedLines => Box::<ModifiedLinesEmitter>::default(),
+        }
+    }
+}
+
+/// Writes the rewritten file (in [`FormatMode::Write`]) or a unified diff (in
+/// [`FormatMode::Diff`]) to stdout. This is `ruff format`'s original, pre-`Emitter` behavior.
+#[derive(Debug, Default)]
+pub(crate) struct StdoutEmitter;
+
+impl Emitter for StdoutEmitter {
+    fn emit(
+        &mut self,
+        path: Option<&Path>,
+        mode: FormatMode,
+        original: &str,
+        formatted: &str,
+        result: FormatCommandResult,
+    ) -> Result<(), FormatCommandError> {
+        match stdout_payload(path, mode, original, formatted, result) {
+            Some(payload) => write_stdout(path, &payload),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Computes what [`StdoutEmitter`] should write for a given `mode` and `result`, or `None` if
+/// nothing should be written.
+fn stdout_payload(
+    path: Option<&Path>,
+    mode: FormatMode,
+    original: &str,
+    formatted: &str,
+    result: FormatCommandResult,
+) -> Option<Vec<u8>> {
+    if result.is_unchanged() {
+        return None;
+    }
+
+    match mode {
+        FormatMode::Write => Some(formatted.as_bytes().to_vec()),
+        FormatMode::Diff => Some(unified_diff(path, original, formatted).into_bytes()),
+        FormatMode::Check => None,
+    }
+}
+
+/// Accumulates one `{ "name": ..., "diff": [...] }` record per changed file, and writes them out
+/// as a single JSON array on [`Emitter::finish`].
+#[derive(Debug, Default)]
+pub(crate) struct JsonEmitter {
+    records: Vec<serde_json::Value>,
+}
+
+impl Emitter for JsonEmitter {
+    fn emit(
+        &mut self,
+        path: Option<&Path>,
+        _mode: FormatMode,
+        original: &str,
+        formatted: &str,
+        result: FormatCommandResult,
+    ) -> Result<(), FormatCommandError> {
+        if result.is_unchanged() {
+            return Ok(());
+        }
+
+        self.records.push(json_record(path, original, formatted));
+        Ok(())
+    }
+
+    fn finish(&mut self) -> Result<(), FormatCommandError> {
+        let payload = serde_json::Value::Array(std::mem::take(&mut self.records));
+        write_stdout(None, payload.to_string().as_bytes())
+    }
+}
+
+/// Builds the `{ "name": ..., "diff": [...] }` record for a single changed file.
+fn json_record(path: Option<&Path>, original: &str, formatted: &str) -> serde_json::Value {
+    let diff = unified_diff(path, original, formatted);
+    serde_json::json!({
+        "name": path.map(Path::to_string_lossy),
+        "diff": diff.lines().collect::<Vec<_>>(),
+    })
+}
+
+/// Builds up a checkstyle XML report, one `<file>` element per file, and writes the full
+/// document on [`Emitter::finish`].
+#[derive(Debug, Default)]
+pub(crate) struct CheckstyleEmitter {
+    files: String,
+}
+
+impl Emitter for CheckstyleEmitter {
+    fn emit(
+        &mut self,
+        path: Option<&Path>,
+        _mode: FormatMode,
+        _original: &str,
+        _formatted: &str,
+        result: FormatCommandResult,
+    ) -> Result<(), FormatCommandError> {
+        self.files.push_str(&checkstyle_file_element(path, result));
+        Ok(())
+    }
+
+    fn finish(&mut self) -> Result<(), FormatCommandError> {
+        let document = format!(
+            "<?xml version=\"1.0\" encoding=\"utf-8\"?><checkstyle version=\"4.3\">{}</checkstyle>",
+            self.files
+        );
+        write_stdout(None, document.as_bytes())
+    }
+}
+
+/// Builds the `<file>` element for a single file: an `<error>` child if it would be reformatted,
+/// otherwise an empty element.
+fn checkstyle_file_element(path: Option<&Path>, result: FormatCommandResult) -> String {
+    let name = escape_xml(&path.map_or_else(|| "-".to_string(), |path| path.display().to_string()));
+    if result.is_formatted() {
+        let message = escape_xml("would reformat");
+        format!(
+            "<file name=\"{name}\"><error line=\"1\" column=\"1\" severity=\"warning\" message=\"{message}\"/></file>"
+        )
+    } else {
+        format!("<file name=\"{name}\"/>")
+    }
+}
+
+/// Emits a stable, machine-readable changeset: one `<start> <removed> <added>` header per
+/// contiguous change (1-based, original-file line numbers), followed by the `added` line
+/// contents, with no surrounding context. A sentinel chunk of `0 0 0` is written once every file
+/// has been emitted, so a reader can tell the stream is complete without re-reading the whole
+/// file, as an LSP server applying incremental edits would.
+#[derive(Debug, Default)]
+pub(crate) struct ModifiedLinesEmitter;
+
+impl Emitter for ModifiedLinesEmitter {
+    fn emit(
+        &mut self,
+        path: Option<&Path>,
+        _mode: FormatMode,
+        original: &str,
+        formatted: &str,
+        result: FormatCommandResult,
+    ) -> Result<(), FormatCommandError> {
+        if result.is_unchanged() {
+            return Ok(());
+        }
+
+        write_stdout(path, modified_lines_chunks(original, formatted).as_bytes())
+    }
+
+    fn finish(&mut self) -> Result<(), FormatCommandError> {
+        write_stdout(None, b"0 0 0\n")
+    }
+}
+
+/// Builds the `<start> <removed> <added>` chunks (plus added line contents) describing how
+/// `original` changed into `formatted`.
+fn modified_lines_chunks(original: &str, formatted: &str) -> String {
+    let original_lines = split_lines(original);
+    let formatted_lines = split_lines(formatted);
+
+    let mut output = String::new();
+    for group in diff_lines(&original_lines, &formatted_lines) {
+        let _ = writeln!(
+            output,
+            "{} {} {}",
+            group.removed.start + 1,
+            group.removed.len(),
+            group.added.len()
+        );
+        for line in &group.added {
+            let _ = writeln!(output, "{line}");
+        }
+    }
+    output
+}
+
+fn write_stdout(path: Option<&Path>, bytes: &[u8]) -> Result<(), FormatCommandError> {
+    stdout()
+        .lock()
+        .write_all(bytes)
+        .map_err(|err| FormatCommandError::Write(path.map(Path::to_path_buf), err))
+}
+
+/// Escapes the characters that are special in XML attribute and text content (`&`, `<`, `>`,
+/// `"`, `'`), so that untrusted strings like file paths can be interpolated into
+/// [`CheckstyleEmitter`]'s output safely.
+fn escape_xml(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+    use crate::commands::format::FormatCommandResult;
+
+    #[test]
+    fn escape_xml_escapes_all_special_characters() {
+        assert_eq!(
+            escape_xml(r#"<a & "b" 'c'>"#),
+            "&lt;a &amp; &quot;b&quot; &apos;c&apos;&gt;"
+        );
+    }
+
+    #[test]
+    fn escape_xml_leaves_plain_text_unchanged() {
+        assert_eq!(escape_xml("src/main.py"), "src/main.py");
+    }
+
+    #[test]
+    fn checkstyle_file_element_escapes_the_file_name() {
+        let path = Path::new("a & b.py");
+        let element = checkstyle_file_element(Some(path), FormatCommandResult::Formatted);
+
+        assert_eq!(
+            element,
+            "<file name=\"a &amp; b.py\"><error line=\"1\" column=\"1\" severity=\"warning\" message=\"would reformat\"/></file>"
+        );
+    }
+
+    #[test]
+    fn checkstyle_file_element_is_empty_for_unchanged_files() {
+        let path = Path::new("main.py");
+        let element = checkstyle_file_element(Some(path), FormatCommandResult::Unchanged);
+
+        assert_eq!(element, "<file name=\"main.py\"/>");
+    }
+
+    #[test]
+    fn stdout_payload_is_none_when_unchanged() {
+        assert!(stdout_payload(
+            None,
+            FormatMode::Write,
+            "a",
+            "a",
+            FormatCommandResult::Unchanged
+        )
+        .is_none());
+    }
+
+    #[test]
+    fn stdout_payload_writes_formatted_contents_in_write_mode() {
+        let payload = stdout_payload(
+            None,
+            FormatMode::Write,
+            "a\n",
+            "a = 1\n",
+            FormatCommandResult::Formatted,
+        )
+        .unwrap();
+        assert_eq!(payload, b"a = 1\n");
+    }
+
+    #[test]
+    fn stdout_payload_writes_a_diff_in_diff_mode() {
+        let payload = stdout_payload(
+            None,
+            FormatMode::Diff,
+            "a\n",
+            "a = 1\n",
+            FormatCommandResult::Formatted,
+        )
+        .unwrap();
+        assert!(String::from_utf8(payload).unwrap().contains("-a\n+a = 1\n"));
+    }
+
+    #[test]
+    fn stdout_payload_is_none_in_check_mode() {
+        assert!(stdout_payload(
+            None,
+            FormatMode::Check,
+            "a\n",
+            "a = 1\n",
+            FormatCommandResult::Formatted
+        )
+        .is_none());
+    }
+
+    #[test]
+    fn json_record_includes_the_name_and_diff() {
+        let record = json_record(Some(Path::new("main.py")), "a\n", "a = 1\n");
+        assert_eq!(record["name"], "main.py");
+        assert!(record["diff"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .any(|line| line == "-a"));
+    }
+
+    #[test]
+    fn modified_lines_chunks_reports_one_header_per_change() {
+        let chunks = modified_lines_chunks("a\nb\nc\n", "a\nx\nc\n");
+        assert_eq!(chunks, "2 1 1\nx\n");
+    }
+
+    #[test]
+    fn json_record_reports_a_line_ending_only_change() {
+        // An explicit `--line-ending windows` override on an all-LF file with no other needed
+        // change must still surface a non-empty diff, not just a bare "this file changed" record.
+        let record = json_record(Some(Path::new("main.py")), "a\nb\n", "a\r\nb\r\n");
+        assert!(
+            record["diff"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .any(|line| line.as_str().unwrap().starts_with('@')),
+            "expected a hunk describing the changed line endings, got: {record}"
+        );
+    }
+
+    #[test]
+    fn modified_lines_chunks_reports_a_line_ending_only_change() {
+        let chunks = modified_lines_chunks("a\nb\n", "a\r\nb\r\n");
+        assert_eq!(chunks, "1 2 2\na\r\nb\r\n");
+    }
+}
@@ -0,0 +1,252 @@
+//! Support for formatting only a subset of a file's lines, mirroring rustfmt's
+//! `FileLines`/`Range`.
+
+use std::num::NonZeroUsize;
+use std::ops::RangeInclusive;
+use std::path::Path;
+use std::str::FromStr;
+
+use rustpython_parser::ast::Location;
+use rustpython_parser::parser::parse_program;
+
+use ruff_python_formatter::{format_module_source, PyFormatOptions};
+use ruff_python_semantic::node::Nodes;
+
+use super::FormatCommandError;
+
+/// A single 1-based, inclusive range of lines requested via `--line-ranges`.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct LineRange {
+    pub(crate) start: NonZeroUsize,
+    pub(crate) end: NonZeroUsize,
+}
+
+impl LineRange {
+    /// Returns `true` if `lines` (a 1-based, inclusive range) falls entirely within `self`.
+    fn contains(&self, lines: RangeInclusive<usize>) -> bool {
+        self.start.get() <= *lines.start() && *lines.end() <= self.end.get()
+    }
+}
+
+/// An error returned when a `--line-ranges` value can't be parsed as `<start>-<end>`.
+#[derive(Debug, thiserror::Error)]
+pub(crate) enum LineRangeParseError {
+    #[error("expected `<start>-<end>`, found {0:?}")]
+    InvalidFormat(String),
+    #[error("invalid line number {0:?}: {1}")]
+    InvalidNumber(String, std::num::ParseIntError),
+    #[error("start line {0} is greater than end line {1}")]
+    StartAfterEnd(NonZeroUsize, NonZeroUsize),
+}
+
+impl FromStr for LineRange {
+    type Err = LineRangeParseError;
+
+    /// Parses a `--line-ranges` value of the form `<start>-<end>` (1-based, inclusive).
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (start, end) = s
+            .split_once('-')
+            .ok_or_else(|| LineRangeParseError::InvalidFormat(s.to_string()))?;
+
+        let parse = |value: &str| {
+            value
+                .parse::<NonZeroUsize>()
+                .map_err(|err| LineRangeParseError::InvalidNumber(value.to_string(), err))
+        };
+        let start = parse(start)?;
+        let end = parse(end)?;
+
+        if start > end {
+            return Err(LineRangeParseError::StartAfterEnd(start, end));
+        }
+
+        Ok(LineRange { start, end })
+    }
+}
+
+/// Formats `source`, but only the top-level statements that fall entirely within `ranges`.
+/// Everything else &mdash; including the indentation and trailing whitespace of untouched
+/// statements &mdash; is carried over byte-for-byte.
+pub(crate) fn format_line_ranges(
+    path: Option<&Path>,
+    source: &str,
+    options: &PyFormatOptions,
+    ranges: &[LineRange],
+) -> Result<String, FormatCommandError> {
+    let python_ast = parse_program(source, "<filename>")
+        .map_err(|err| FormatCommandError::Parse(path.map(Path::to_path_buf), err))?;
+
+    // Build the statement tree so we can walk the module's top-level (`depth == 0`) statements,
+    // the same granularity at which this partitioning scheme operates.
+    let mut nodes = Nodes::default();
+    for stmt in &python_ast {
+        nodes.insert(stmt, None);
+    }
+
+    let mut output = String::with_capacity(source.len());
+    let mut cursor = 0;
+    // The pending in-range block, as `(block_start, block_end, range_index)`. `range_index`
+    // identifies which of `ranges` the block's statements matched, so that two statements
+    // matching *different* ranges (with an untouched gap between them, however small) are never
+    // merged into a single reformatted span.
+    let mut pending_block: Option<(usize, usize, usize)> = None;
+
+    for id in nodes.top_level_ids() {
+        let stmt = nodes[id];
+        let start_line = stmt.location.row();
+        let end_line = stmt.end_location.map_or(start_line, Location::row);
+
+        let matching_range = ranges
+            .iter()
+            .position(|range| range.contains(start_line..=end_line));
+
+        match (matching_range, pending_block) {
+            (Some(range_index), Some((block_start, _, pending_range_index)))
+                if range_index == pending_range_index =>
+            {
+                let stmt_end = offset(source, stmt.end_location.unwrap_or(stmt.location));
+                pending_block = Some((block_start, stmt_end, range_index));
+            }
+            (Some(range_index), _) => {
+                if let Some((block_start, block_end, _)) = pending_block.take() {
+                    flush_block(path, source, options, block_start, block_end, &mut output, &mut cursor)?;
+                }
+                let stmt_start = offset(source, stmt.location);
+                let stmt_end = offset(source, stmt.end_location.unwrap_or(stmt.location));
+                pending_block = Some((stmt_start, stmt_end, range_index));
+            }
+            (None, _) => {
+                if let Some((block_start, block_end, _)) = pending_block.take() {
+                    flush_block(path, source, options, block_start, block_end, &mut output, &mut cursor)?;
+                }
+            }
+        }
+    }
+
+    if let Some((block_start, block_end, _)) = pending_block {
+        flush_block(path, source, options, block_start, block_end, &mut output, &mut cursor)?;
+    }
+
+    output.push_str(&source[cursor..]);
+    Ok(output)
+}
+
+/// Appends `source[cursor..block_start]` verbatim, then the reformatted
+/// `source[block_start..block_end]`, advancing `cursor` to `block_end`.
+fn flush_block(
+    path: Option<&Path>,
+    source: &str,
+    options: &PyFormatOptions,
+    block_start: usize,
+    block_end: usize,
+    output: &mut String,
+    cursor: &mut usize,
+) -> Result<(), FormatCommandError> {
+    output.push_str(&source[*cursor..block_start]);
+    let formatted = format_module_source(&source[block_start..block_end], options.clone())
+        .map_err(|err| FormatCommandError::FormatModule(path.map(Path::to_path_buf), err))?;
+    output.push_str(formatted.as_code().trim_end_matches('\n'));
+    *cursor = block_end;
+    Ok(())
+}
+
+/// Converts a parser [`Location`] (1-based row, 0-based character column) into a byte offset
+/// into `source`.
+///
+/// `column` is a *character* count, not a byte count, so it can't simply be added to the line's
+/// starting byte offset: a line containing multibyte UTF-8 content before the target column
+/// would under-count. Instead, walk `column` characters into the line and sum their UTF-8
+/// lengths.
+fn offset(source: &str, location: Location) -> usize {
+    let line_start: usize = source
+        .split('\n')
+        .take(location.row() - 1)
+        .map(|line| line.len() + 1)
+        .sum();
+    let column_bytes: usize = source[line_start..]
+        .chars()
+        .take(location.column())
+        .map(char::len_utf8)
+        .sum();
+    line_start + column_bytes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn range(start: usize, end: usize) -> LineRange {
+        LineRange {
+            start: NonZeroUsize::new(start).unwrap(),
+            end: NonZeroUsize::new(end).unwrap(),
+        }
+    }
+
+    #[test]
+    fn line_range_from_str_parses_start_and_end() {
+        let parsed: LineRange = "2-5".parse().unwrap();
+        assert_eq!(parsed.start.get(), 2);
+        assert_eq!(parsed.end.get(), 5);
+    }
+
+    #[test]
+    fn line_range_from_str_rejects_missing_separator() {
+        assert!(matches!(
+            "25".parse::<LineRange>(),
+            Err(LineRangeParseError::InvalidFormat(_))
+        ));
+    }
+
+    #[test]
+    fn line_range_from_str_rejects_start_after_end() {
+        assert!(matches!(
+            "5-2".parse::<LineRange>(),
+            Err(LineRangeParseError::StartAfterEnd(_, _))
+        ));
+    }
+
+    #[test]
+    fn line_range_contains_checks_both_bounds() {
+        let requested = range(2, 5);
+        assert!(requested.contains(2..=5));
+        assert!(requested.contains(3..=4));
+        assert!(!requested.contains(1..=5));
+        assert!(!requested.contains(2..=6));
+    }
+
+    #[test]
+    fn offset_counts_multibyte_characters_by_byte_length() {
+        // `é` is a 2-byte character, so the 4th character ('l') starts 1 byte later than its
+        // character index alone would suggest.
+        let source = "héllo\nworld\n";
+        assert_eq!(&source[offset(source, Location::new(1, 3))..], "lo\nworld\n");
+    }
+
+    #[test]
+    fn offset_accounts_for_preceding_lines() {
+        let source = "héllo\nworld\n";
+        assert_eq!(&source[offset(source, Location::new(2, 2))..], "rld\n");
+    }
+
+    #[test]
+    fn format_line_ranges_preserves_crlf_outside_requested_range() {
+        let source = "x = 1\r\ny   =   2\r\nz = 3\r\n";
+        let options = PyFormatOptions::default();
+        let formatted = format_line_ranges(None, source, &options, &[range(1, 1)]).unwrap();
+
+        // Line 2 was never requested, so its original spacing and CRLF must survive untouched.
+        assert!(formatted.contains("y   =   2\r\n"));
+    }
+
+    #[test]
+    fn format_line_ranges_keeps_disjoint_ranges_from_merging_the_gap_between_them() {
+        let source = "a = 1\nb   =   2\nc = 3\n";
+        let options = PyFormatOptions::default();
+        // `a` and `c` each match a range, but line 2 between them belongs to neither `1-1` nor
+        // `3-3` and must be preserved.
+        let formatted =
+            format_line_ranges(None, source, &options, &[range(1, 1), range(3, 3)]).unwrap();
+
+        assert!(formatted.contains("b   =   2\n"));
+    }
+}
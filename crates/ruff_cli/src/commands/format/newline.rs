@@ -0,0 +1,135 @@
+//! Detects and enforces a consistent line-ending style across the input and formatted source,
+//! mirroring rustfmt's `NewlineStyle`.
+
+/// The requested line-ending style to use when emitting formatted source, settable via
+/// `--line-ending`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub(crate) enum NewlineStyle {
+    /// Detect the dominant line ending of the input, and use that.
+    #[default]
+    Auto,
+    /// Use the line ending native to the platform `ruff` is running on.
+    Native,
+    /// Always use `\n`.
+    Unix,
+    /// Always use `\r\n`.
+    Windows,
+}
+
+/// A concrete line ending, resolved from a [`NewlineStyle`] and (for `Auto`) the input source.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum LineEnding {
+    Unix,
+    Windows,
+}
+
+impl LineEnding {
+    const fn as_str(self) -> &'static str {
+        match self {
+            LineEnding::Unix => "\n",
+            LineEnding::Windows => "\r\n",
+        }
+    }
+
+    /// Returns the line ending native to the platform `ruff` is running on.
+    fn native() -> Self {
+        if cfg!(windows) {
+            LineEnding::Windows
+        } else {
+            LineEnding::Unix
+        }
+    }
+
+    /// Rewrites every line ending in `source` to match this line ending.
+    ///
+    /// `source` is not assumed to contain only bare `\n`: when `--line-ranges` splices in
+    /// untouched spans of the original file verbatim, those spans may already contain `\r\n`. A
+    /// blanket `replace('\n', "\r\n")` would double the `\r` in that case, so a `\r` that already
+    /// precedes a `\n` is left alone; only a *lone* `\n` gets one inserted.
+    pub(crate) fn apply(self, source: &str) -> String {
+        if self == LineEnding::Unix {
+            return source.replace("\r\n", "\n");
+        }
+
+        let mut result = String::with_capacity(source.len());
+        let mut chars = source.chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                '\r' if chars.peek() == Some(&'\n') => {
+                    // Drop the bare `\r`; the `\n` handled next emits the full line ending.
+                }
+                '\n' => result.push_str(self.as_str()),
+                c => result.push(c),
+            }
+        }
+        result
+    }
+}
+
+impl std::fmt::Display for NewlineStyle {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        use clap::ValueEnum;
+        self.to_possible_value()
+            .expect("no values are skipped")
+            .get_name()
+            .fmt(f)
+    }
+}
+
+impl NewlineStyle {
+    /// Resolves this style to a concrete [`LineEnding`], detecting the dominant line ending of
+    /// `source` for [`NewlineStyle::Auto`] (the first line break wins).
+    pub(crate) fn resolve(self, source: &str) -> LineEnding {
+        match self {
+            NewlineStyle::Auto => detect(source),
+            NewlineStyle::Native => LineEnding::native(),
+            NewlineStyle::Unix => LineEnding::Unix,
+            NewlineStyle::Windows => LineEnding::Windows,
+        }
+    }
+}
+
+/// Detects the dominant line ending of `source` from its first line break, defaulting to
+/// [`LineEnding::Unix`] if `source` contains none.
+fn detect(source: &str) -> LineEnding {
+    match source.find('\n') {
+        Some(index) if index > 0 && source.as_bytes()[index - 1] == b'\r' => LineEnding::Windows,
+        _ => LineEnding::Unix,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detect_finds_windows_line_endings() {
+        assert_eq!(detect("a\r\nb\r\n"), LineEnding::Windows);
+    }
+
+    #[test]
+    fn detect_finds_unix_line_endings() {
+        assert_eq!(detect("a\nb\n"), LineEnding::Unix);
+    }
+
+    #[test]
+    fn apply_windows_inserts_cr_only_before_bare_newlines() {
+        assert_eq!(LineEnding::Windows.apply("a\nb\n"), "a\r\nb\r\n");
+    }
+
+    #[test]
+    fn apply_windows_does_not_double_existing_crlf() {
+        // Regression test: spliced-in spans from `--line-ranges` may already be `\r\n`.
+        assert_eq!(LineEnding::Windows.apply("a\r\nb\r\n"), "a\r\nb\r\n");
+    }
+
+    #[test]
+    fn apply_windows_handles_mixed_line_endings() {
+        assert_eq!(LineEnding::Windows.apply("a\r\nb\nc\r\n"), "a\r\nb\r\nc\r\n");
+    }
+
+    #[test]
+    fn apply_unix_normalizes_existing_crlf() {
+        assert_eq!(LineEnding::Unix.apply("a\r\nb\n"), "a\nb\n");
+    }
+}
@@ -1,4 +1,3 @@
-use std::io::{stdout, Write};
 use std::path::Path;
 
 use anyhow::Result;
@@ -9,7 +8,10 @@ use ruff_python_formatter::{format_module_source, PyFormatOptions};
 use ruff_workspace::resolver::python_file_at_path;
 
 use crate::args::{CliOverrides, FormatArguments};
-use crate::commands::format::{FormatCommandError, FormatCommandResult, FormatMode};
+use crate::commands::format::{
+    format_line_ranges, Emitter, FormatCommandError, FormatCommandResult, FormatMode, LineRange,
+    NewlineStyle,
+};
 use crate::resolve::resolve;
 use crate::stdin::read_from_stdin;
 use crate::ExitStatus;
@@ -24,6 +26,8 @@ pub(crate) fn format_stdin(cli: &FormatArguments, overrides: &CliOverrides) -> R
     )?;
     let mode = if cli.check {
         FormatMode::Check
+    } else if cli.diff {
+        FormatMode::Diff
     } else {
         FormatMode::Write
     };
@@ -42,17 +46,28 @@ pub(crate) fn format_stdin(cli: &FormatArguments, overrides: &CliOverrides) -> R
         .formatter
         .to_format_options(path.map(PySourceType::from).unwrap_or_default());
 
-    match format_source(path, options, mode) {
-        Ok(result) => match mode {
-            FormatMode::Write => Ok(ExitStatus::Success),
-            FormatMode::Check => {
-                if result.is_formatted() {
-                    Ok(ExitStatus::Failure)
-                } else {
-                    Ok(ExitStatus::Success)
+    match format_source(path, options, &cli.line_ranges, cli.line_ending) {
+        Ok((unformatted, formatted, result)) => {
+            let mut emitter = cli.output_format.to_emitter();
+            if let Err(err) = emitter
+                .emit(path, mode, &unformatted, &formatted, result)
+                .and_then(|()| emitter.finish())
+            {
+                warn!("{err}");
+                return Ok(ExitStatus::Error);
+            }
+
+            match mode {
+                FormatMode::Write => Ok(ExitStatus::Success),
+                FormatMode::Check | FormatMode::Diff => {
+                    if result.is_formatted() {
+                        Ok(ExitStatus::Failure)
+                    } else {
+                        Ok(ExitStatus::Success)
+                    }
                 }
             }
-        },
+        }
         Err(err) => {
             warn!("{err}");
             Ok(ExitStatus::Error)
@@ -60,26 +75,31 @@ pub(crate) fn format_stdin(cli: &FormatArguments, overrides: &CliOverrides) -> R
     }
 }
 
-/// Format source code read from `stdin`.
+/// Formats source code read from `stdin`, returning the original and formatted source alongside
+/// the outcome. Writing the result anywhere is the caller's responsibility, via an [`Emitter`].
 fn format_source(
     path: Option<&Path>,
     options: PyFormatOptions,
-    mode: FormatMode,
-) -> Result<FormatCommandResult, FormatCommandError> {
+    line_ranges: &[LineRange],
+    line_ending: NewlineStyle,
+) -> Result<(String, String, FormatCommandResult), FormatCommandError> {
     let unformatted = read_from_stdin()
         .map_err(|err| FormatCommandError::Read(path.map(Path::to_path_buf), err))?;
-    let formatted = format_module_source(&unformatted, options)
-        .map_err(|err| FormatCommandError::FormatModule(path.map(Path::to_path_buf), err))?;
-    let formatted = formatted.as_code();
-    if formatted.len() == unformatted.len() && formatted == unformatted {
-        Ok(FormatCommandResult::Unchanged)
+    let formatted = if line_ranges.is_empty() {
+        format_module_source(&unformatted, options)
+            .map_err(|err| FormatCommandError::FormatModule(path.map(Path::to_path_buf), err))?
+            .as_code()
+            .to_string()
     } else {
-        if mode.is_write() {
-            stdout()
-                .lock()
-                .write_all(formatted.as_bytes())
-                .map_err(|err| FormatCommandError::Write(path.map(Path::to_path_buf), err))?;
-        }
-        Ok(FormatCommandResult::Formatted)
-    }
+        format_line_ranges(path, &unformatted, &options, line_ranges)?
+    };
+    // The formatter always emits `\n`, so rewrite its output to match the requested line-ending
+    // style (or the input's own, for `NewlineStyle::Auto`) before comparing or writing it back.
+    let formatted = line_ending.resolve(&unformatted).apply(&formatted);
+    let result = if formatted.len() == unformatted.len() && formatted == unformatted {
+        FormatCommandResult::Unchanged
+    } else {
+        FormatCommandResult::Formatted
+    };
+    Ok((unformatted, formatted, result))
 }
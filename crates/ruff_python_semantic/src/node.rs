@@ -95,6 +95,16 @@ impl<'a> Nodes<'a> {
         let parent_id = self.nodes[usize::from(*node_id)].parent?;
         Some(self[parent_id])
     }
+
+    /// Returns an iterator over the [`NodeId`]s of all top-level (`depth == 0`) nodes, in
+    /// insertion order.
+    pub fn top_level_ids(&self) -> impl Iterator<Item = NodeId> + '_ {
+        self.nodes
+            .iter()
+            .enumerate()
+            .filter(|(_, node)| node.depth == 0)
+            .map(|(index, _)| NodeId::try_from(index).unwrap())
+    }
 }
 
 impl<'a> Index<NodeId> for Nodes<'a> {